@@ -1,8 +1,16 @@
 use actix::prelude::*;
+use bytes::{Buf, BytesMut};
+use flate2::read::{GzDecoder, ZlibDecoder};
 use serde::de::{Error, Unexpected};
 use serde::{Deserialize, Serialize};
 use serde_json::{Error as JsonError, Map, Result as JsonResult, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio_util::codec::Decoder;
 
 /// Struct, which contains gelf data
 pub struct GelfDataWrapper {
@@ -10,15 +18,31 @@ pub struct GelfDataWrapper {
 }
 
 impl GelfDataWrapper {
-    /// Create gelf data wrapper from json slice
+    /// Create gelf data wrapper from a json slice, transparently decompressing
+    /// GZIP or ZLIB payloads (the two compressions GELF senders commonly emit).
     pub fn from_slice(buf: &[u8]) -> JsonResult<GelfDataWrapper> {
-        let data: Map<String, Value> = serde_json::from_slice(buf)?;
-
+        let data = Self::parse(buf)?;
         let data = to_gelf(data)?;
 
         Ok(GelfDataWrapper { data })
     }
 
+    /// Like `from_slice`, but additionally enforces the GELF 1.1 spec rules
+    /// (exact version string, non-empty `short_message`, well-formed and
+    /// non-reserved additional fields), rejecting anything that merely
+    /// looks close enough.
+    pub fn from_slice_strict(buf: &[u8]) -> JsonResult<GelfDataWrapper> {
+        let data = Self::parse(buf)?;
+        let data = to_gelf_strict(data)?;
+
+        Ok(GelfDataWrapper { data })
+    }
+
+    fn parse(buf: &[u8]) -> JsonResult<Map<String, Value>> {
+        let decompressed = decompress(buf)?;
+        serde_json::from_slice(&decompressed)
+    }
+
     /// print gelf data to stdio
     pub fn print(&self) {
         println!("{}", self.to_string());
@@ -28,6 +52,37 @@ impl GelfDataWrapper {
     pub fn into_gelf(self) -> GelfData {
         self.data
     }
+
+    /// Reconstruct a spec-correct GELF document from the internal
+    /// `meta`/`mechanism_data` split: `meta` entries are re-prefixed with
+    /// `_` and `mechanism_data` entries are merged back in at the root,
+    /// undoing what `to_gelf` did on the way in.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn into_gelf_json(&self) -> Value {
+        let mut obj = Map::new();
+        obj.insert("version".to_string(), Value::String(self.data.version.clone()));
+        obj.insert("host".to_string(), Value::String(self.data.host.clone()));
+        obj.insert(
+            "short_message".to_string(),
+            Value::String(self.data.short_message.clone()),
+        );
+        obj.insert("timestamp".to_string(), Value::from(self.data.timestamp));
+        obj.insert("level".to_string(), Value::from(self.data.level.as_u8()));
+
+        for (key, value) in &self.data.meta {
+            obj.insert(format!("_{}", key), value.clone());
+        }
+        for (key, value) in &self.data.mechanism_data {
+            obj.insert(key.clone(), value.clone());
+        }
+
+        Value::Object(obj)
+    }
+
+    /// Serialize the canonical GELF document as a single NDJSON line.
+    pub fn to_gelf_string(&self) -> String {
+        serde_json::to_string(&self.into_gelf_json()).unwrap()
+    }
 }
 
 impl ToString for GelfDataWrapper {
@@ -42,11 +97,23 @@ impl Message for GelfMessage {
     type Result = JsonResult<GelfDataWrapper>;
 }
 
-pub struct GelfReaderActor;
+pub struct GelfReaderActor {
+    strict: bool,
+}
 
 impl GelfReaderActor {
     pub fn new(threads: usize) -> Addr<GelfReaderActor> {
-        SyncArbiter::start(threads, || GelfReaderActor)
+        Self::with_strict(threads, false)
+    }
+
+    /// Start a reader actor that rejects input failing GELF 1.1 spec validation
+    /// instead of best-effort parsing it.
+    pub fn new_strict(threads: usize) -> Addr<GelfReaderActor> {
+        Self::with_strict(threads, true)
+    }
+
+    fn with_strict(threads: usize, strict: bool) -> Addr<GelfReaderActor> {
+        SyncArbiter::start(threads, move || GelfReaderActor { strict })
     }
 }
 
@@ -58,7 +125,202 @@ impl Handler<GelfMessage> for GelfReaderActor {
     type Result = JsonResult<GelfDataWrapper>;
 
     fn handle(&mut self, GelfMessage(msg): GelfMessage, _ctx: &mut Self::Context) -> Self::Result {
-        GelfDataWrapper::from_slice(msg.as_slice())
+        if self.strict {
+            GelfDataWrapper::from_slice_strict(msg.as_slice())
+        } else {
+            GelfDataWrapper::from_slice(msg.as_slice())
+        }
+    }
+}
+
+/// GELF/UDP chunk framing magic bytes, per the GELF 1.1 spec.
+const GELF_CHUNK_MAGIC: [u8; 2] = [0x1e, 0x0f];
+
+/// Maximum number of chunks a single GELF message may be split into.
+const GELF_MAX_CHUNKS: usize = 128;
+
+/// A raw datagram as received from the wire, which may or may not be chunked.
+pub struct GelfDatagram(pub Vec<u8>);
+
+impl Message for GelfDatagram {
+    type Result = ();
+}
+
+struct PendingMessage {
+    chunks: Vec<Option<Vec<u8>>>,
+    received: usize,
+    first_seen: Instant,
+}
+
+/// Sits in front of `GelfReaderActor` and reassembles chunked GELF/UDP
+/// datagrams before forwarding the complete payload as a `GelfMessage`.
+///
+/// Datagrams that don't carry the GELF chunk magic are passed through
+/// unchanged. Partial messages that never collect all of their chunks are
+/// evicted after `timeout` so dropped chunks don't leak memory.
+pub struct GelfChunkReassembler {
+    reader: Addr<GelfReaderActor>,
+    timeout: Duration,
+    pending: HashMap<[u8; 8], PendingMessage>,
+}
+
+impl GelfChunkReassembler {
+    /// Create a reassembler with the spec default 5 second chunk timeout.
+    pub fn new(reader: Addr<GelfReaderActor>) -> Addr<GelfChunkReassembler> {
+        Self::with_timeout(reader, Duration::from_secs(5))
+    }
+
+    /// Create a reassembler with a configurable chunk timeout.
+    pub fn with_timeout(reader: Addr<GelfReaderActor>, timeout: Duration) -> Addr<GelfChunkReassembler> {
+        Actor::create(|_ctx| GelfChunkReassembler {
+            reader,
+            timeout,
+            pending: HashMap::new(),
+        })
+    }
+
+    fn evict_expired(&mut self) {
+        let timeout = self.timeout;
+        self.pending.retain(|_, msg| msg.first_seen.elapsed() < timeout);
+    }
+
+    /// Feed one datagram into the reassembler, returning the complete
+    /// message payload once every chunk of a message has arrived.
+    fn process_datagram(&mut self, datagram: Vec<u8>) -> Option<Vec<u8>> {
+        // Chunk header: 2 magic bytes, 8 byte message id, sequence number, sequence count.
+        if datagram.len() < 12 || datagram[0..2] != GELF_CHUNK_MAGIC {
+            return Some(datagram);
+        }
+
+        let mut message_id = [0u8; 8];
+        message_id.copy_from_slice(&datagram[2..10]);
+        let sequence_number = datagram[10] as usize;
+        let sequence_count = datagram[11] as usize;
+        let payload = datagram[12..].to_vec();
+
+        if sequence_count == 0 || sequence_count > GELF_MAX_CHUNKS || sequence_number >= sequence_count {
+            return None;
+        }
+
+        let pending = self.pending.entry(message_id).or_insert_with(|| PendingMessage {
+            chunks: vec![None; sequence_count],
+            received: 0,
+            first_seen: Instant::now(),
+        });
+
+        // `sequence_count` above is only validated against *this* datagram; the
+        // entry may already exist with a different (smaller) chunk count from an
+        // earlier datagram for the same message id, so re-check against it.
+        if sequence_number >= pending.chunks.len() {
+            return None;
+        }
+
+        if pending.chunks[sequence_number].is_some() {
+            return None; // duplicate chunk for an already-seen index
+        }
+
+        pending.chunks[sequence_number] = Some(payload);
+        pending.received += 1;
+
+        if pending.received != pending.chunks.len() {
+            return None;
+        }
+
+        let pending = self.pending.remove(&message_id).unwrap();
+        let mut buf = Vec::new();
+        for chunk in pending.chunks.into_iter().flatten() {
+            buf.extend_from_slice(&chunk);
+        }
+        Some(buf)
+    }
+}
+
+impl Actor for GelfChunkReassembler {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(Duration::from_secs(1), |actor, _ctx| actor.evict_expired());
+    }
+}
+
+impl Handler<GelfDatagram> for GelfChunkReassembler {
+    type Result = ();
+
+    fn handle(&mut self, GelfDatagram(datagram): GelfDatagram, _ctx: &mut Self::Context) {
+        if let Some(msg) = self.process_datagram(datagram) {
+            self.reader.do_send(GelfMessage(msg));
+        }
+    }
+}
+
+/// Default cap on how large a single frame may grow while waiting for its
+/// delimiter, so a misbehaving connection that never sends `0x00` can't
+/// grow the buffer without bound.
+const GELF_STREAM_MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Splits a continuous GELF/TCP byte stream into individual `GelfMessage`s,
+/// delimited by a single null byte with no chunking or compression.
+pub struct GelfStreamCodec {
+    max_frame_length: usize,
+}
+
+impl GelfStreamCodec {
+    pub fn new() -> GelfStreamCodec {
+        Self::with_max_frame_length(GELF_STREAM_MAX_FRAME_LENGTH)
+    }
+
+    pub fn with_max_frame_length(max_frame_length: usize) -> GelfStreamCodec {
+        GelfStreamCodec { max_frame_length }
+    }
+}
+
+impl Default for GelfStreamCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub enum GelfStreamCodecError {
+    Io(std::io::Error),
+    FrameTooLarge(usize),
+}
+
+impl fmt::Display for GelfStreamCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GelfStreamCodecError::Io(e) => write!(f, "io error while decoding gelf stream: {}", e),
+            GelfStreamCodecError::FrameTooLarge(len) => {
+                write!(f, "gelf stream frame exceeded the size limit ({} bytes buffered)", len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GelfStreamCodecError {}
+
+impl From<std::io::Error> for GelfStreamCodecError {
+    fn from(e: std::io::Error) -> Self {
+        GelfStreamCodecError::Io(e)
+    }
+}
+
+impl Decoder for GelfStreamCodec {
+    type Item = GelfMessage;
+    type Error = GelfStreamCodecError;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if let Some(pos) = buf.iter().position(|&b| b == 0x00) {
+            let frame = buf.split_to(pos);
+            buf.advance(1); // drop the delimiter itself
+            return Ok(Some(GelfMessage(frame.to_vec())));
+        }
+
+        if buf.len() > self.max_frame_length {
+            return Err(GelfStreamCodecError::FrameTooLarge(buf.len()));
+        }
+
+        Ok(None)
     }
 }
 
@@ -73,6 +335,26 @@ pub struct GelfData {
     pub mechanism_data: Map<String, Value>,
 }
 
+/// Sniff the magic bytes of `buf` and decompress it if it looks like a GZIP
+/// or ZLIB stream, otherwise pass the bytes through unchanged.
+fn decompress(buf: &[u8]) -> JsonResult<Cow<'_, [u8]>> {
+    if buf.len() >= 2 && buf[0] == 0x1f && buf[1] == 0x8b {
+        let mut out = Vec::new();
+        GzDecoder::new(buf)
+            .read_to_end(&mut out)
+            .map_err(|e| JsonError::custom(format!("gzip decompression failed: {}", e)))?;
+        Ok(Cow::Owned(out))
+    } else if buf.len() >= 2 && buf[0] == 0x78 && matches!(buf[1], 0x01 | 0x9c | 0xda) {
+        let mut out = Vec::new();
+        ZlibDecoder::new(buf)
+            .read_to_end(&mut out)
+            .map_err(|e| JsonError::custom(format!("zlib decompression failed: {}", e)))?;
+        Ok(Cow::Owned(out))
+    } else {
+        Ok(Cow::Borrowed(buf))
+    }
+}
+
 fn to_gelf(data: Map<String, Value>) -> JsonResult<GelfData> {
     let mut meta = Map::new();
     let mut mechanism_data = Map::new();
@@ -99,11 +381,17 @@ fn to_gelf(data: Map<String, Value>) -> JsonResult<GelfData> {
             .as_str()
             .ok_or_else(|| JsonError::invalid_type(Unexpected::Other("host"), &"string"))?
             .to_string(),
-        level: data
-            .get("level")
-            .ok_or_else(|| JsonError::missing_field("level"))?
-            .to_string()
-            .parse::<GelfLevel>()?,
+        level: {
+            let level = data.get("level").ok_or_else(|| JsonError::missing_field("level"))?;
+            // `Value::to_string()` re-quotes JSON strings (`"warning"` becomes
+            // `"\"warning\""`), which breaks `GelfLevel::from_str` for senders
+            // that emit textual levels, so prefer the unquoted string form.
+            level
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| level.to_string())
+                .parse::<GelfLevel>()?
+        },
         short_message: data
             .get("short_message")
             .ok_or_else(|| JsonError::missing_field("short_message"))?
@@ -126,7 +414,54 @@ fn to_gelf(data: Map<String, Value>) -> JsonResult<GelfData> {
     })
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Like `to_gelf`, but first enforces the GELF 1.1 spec rules instead of
+/// silently accepting anything with the five core fields.
+fn to_gelf_strict(data: Map<String, Value>) -> JsonResult<GelfData> {
+    validate_strict(&data)?;
+    to_gelf(data)
+}
+
+fn validate_strict(data: &Map<String, Value>) -> JsonResult<()> {
+    let version = data.get("version").and_then(Value::as_str).unwrap_or_default();
+    if version != "1.1" {
+        return Err(JsonError::custom(format!(
+            "strict GELF 1.1 violation: version must be exactly \"1.1\", got {:?}",
+            version
+        )));
+    }
+
+    let short_message = data.get("short_message").and_then(Value::as_str).unwrap_or_default();
+    if short_message.is_empty() {
+        return Err(JsonError::custom(
+            "strict GELF 1.1 violation: short_message must not be empty",
+        ));
+    }
+
+    for key in data.keys() {
+        if let Some(field) = key.strip_prefix('_') {
+            if key == "_id" {
+                return Err(JsonError::custom(
+                    "strict GELF 1.1 violation: \"_id\" is reserved and may not be used as an additional field",
+                ));
+            }
+            if !is_valid_additional_field_name(field) {
+                return Err(JsonError::custom(format!(
+                    "strict GELF 1.1 violation: additional field \"{}\" does not match ^[\\w.\\-]+$",
+                    key
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Mirrors the regex `^[\w.\-]+$`: one or more word characters, dots or hyphens.
+fn is_valid_additional_field_name(field: &str) -> bool {
+    !field.is_empty() && field.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '-')
+}
+
+#[derive(Debug)]
 pub enum GelfLevel {
     Emergency = 0,
     Alert = 1,
@@ -138,22 +473,78 @@ pub enum GelfLevel {
     Debug = 7,
 }
 
+impl GelfLevel {
+    fn as_u8(&self) -> u8 {
+        match self {
+            GelfLevel::Emergency => 0,
+            GelfLevel::Alert => 1,
+            GelfLevel::Critical => 2,
+            GelfLevel::Error => 3,
+            GelfLevel::Warning => 4,
+            GelfLevel::Notice => 5,
+            GelfLevel::Informational => 6,
+            GelfLevel::Debug => 7,
+        }
+    }
+}
+
+/// Serializes as the plain syslog integer discriminant (0-7), since that's
+/// what the GELF spec requires for the `level` field.
+impl Serialize for GelfLevel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+impl<'de> Deserialize<'de> for GelfLevel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = u8::deserialize(deserializer)?;
+        GelfLevel::try_from(value).map_err(|_| {
+            serde::de::Error::invalid_value(Unexpected::Unsigned(value as u64), &"integers from 0 to 7")
+        })
+    }
+}
+
+impl TryFrom<u8> for GelfLevel {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, ()> {
+        match value {
+            0 => Ok(GelfLevel::Emergency),
+            1 => Ok(GelfLevel::Alert),
+            2 => Ok(GelfLevel::Critical),
+            3 => Ok(GelfLevel::Error),
+            4 => Ok(GelfLevel::Warning),
+            5 => Ok(GelfLevel::Notice),
+            6 => Ok(GelfLevel::Informational),
+            7 => Ok(GelfLevel::Debug),
+            _ => Err(()),
+        }
+    }
+}
+
 impl FromStr for GelfLevel {
     type Err = JsonError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "0" => Ok(GelfLevel::Emergency),
-            "1" => Ok(GelfLevel::Alert),
-            "2" => Ok(GelfLevel::Critical),
-            "3" => Ok(GelfLevel::Error),
-            "4" => Ok(GelfLevel::Warning),
-            "5" => Ok(GelfLevel::Notice),
-            "6" => Ok(GelfLevel::Informational),
-            "7" => Ok(GelfLevel::Debug),
+        match s.to_ascii_lowercase().as_str() {
+            "0" | "emergency" => Ok(GelfLevel::Emergency),
+            "1" | "alert" => Ok(GelfLevel::Alert),
+            "2" | "critical" => Ok(GelfLevel::Critical),
+            "3" | "error" => Ok(GelfLevel::Error),
+            "4" | "warning" => Ok(GelfLevel::Warning),
+            "5" | "notice" => Ok(GelfLevel::Notice),
+            "6" | "informational" | "info" => Ok(GelfLevel::Informational),
+            "7" | "debug" => Ok(GelfLevel::Debug),
             _ => Err(JsonError::invalid_value(
                 Unexpected::Other("level"),
-                &"integers from 0 to 7",
+                &"integers from 0 to 7 or a syslog level name",
             )),
         }
     }
@@ -162,7 +553,10 @@ impl FromStr for GelfLevel {
 #[cfg(test)]
 mod reader {
     use super::*;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use flate2::Compression;
     use serde_json::json;
+    use std::io::Write;
 
     #[test]
     fn test_gelf() {
@@ -211,4 +605,284 @@ mod reader {
         assert_eq!(r.data.meta["some_info"], "foo");
         assert!(matches!(r.data.level, GelfLevel::Notice))
     }
+
+    #[test]
+    fn test_from_slice_decompresses_gzip_and_zlib() {
+        let json = br#"{
+            "version":"1.1",
+            "host":"example.org",
+            "short_message":"A short message",
+            "level":5,
+            "timestamp":1582213226
+        }"#;
+
+        let mut gzip_encoder = GzEncoder::new(Vec::new(), Compression::default());
+        gzip_encoder.write_all(json).unwrap();
+        let gzipped = gzip_encoder.finish().unwrap();
+        let r = GelfDataWrapper::from_slice(&gzipped).unwrap();
+        assert_eq!(r.data.short_message, "A short message");
+
+        let mut zlib_encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib_encoder.write_all(json).unwrap();
+        let zlibbed = zlib_encoder.finish().unwrap();
+        let r = GelfDataWrapper::from_slice(&zlibbed).unwrap();
+        assert_eq!(r.data.short_message, "A short message");
+    }
+
+    #[test]
+    fn test_from_slice_rejects_corrupt_gzip() {
+        let corrupt = [0x1f, 0x8b, 0x00, 0x00];
+        assert!(GelfDataWrapper::from_slice(&corrupt).is_err());
+    }
+
+    #[test]
+    fn test_to_gelf_strict_accepts_spec_compliant_message() {
+        let mut temp = Map::new();
+        temp.insert("version".to_string(), json!("1.1"));
+        temp.insert("host".to_string(), json!("example.org"));
+        temp.insert("short_message".to_string(), json!("A short message"));
+        temp.insert("_some_info".to_string(), json!("foo"));
+        temp.insert("level".to_string(), json!(5));
+        temp.insert("timestamp".to_string(), json!(1_582_213_226));
+
+        assert!(to_gelf_strict(temp).is_ok());
+    }
+
+    #[test]
+    fn test_to_gelf_strict_rejects_violations() {
+        let base = || {
+            let mut temp = Map::new();
+            temp.insert("version".to_string(), json!("1.1"));
+            temp.insert("host".to_string(), json!("example.org"));
+            temp.insert("short_message".to_string(), json!("A short message"));
+            temp.insert("level".to_string(), json!(5));
+            temp.insert("timestamp".to_string(), json!(1_582_213_226));
+            temp
+        };
+
+        let mut bad_version = base();
+        bad_version.insert("version".to_string(), json!("1.0"));
+        assert!(to_gelf_strict(bad_version).is_err());
+
+        let mut empty_message = base();
+        empty_message.insert("short_message".to_string(), json!(""));
+        assert!(to_gelf_strict(empty_message).is_err());
+
+        let mut reserved_id = base();
+        reserved_id.insert("_id".to_string(), json!("123"));
+        assert!(to_gelf_strict(reserved_id).is_err());
+
+        let mut malformed_field = base();
+        malformed_field.insert("_bad field!".to_string(), json!("x"));
+        assert!(to_gelf_strict(malformed_field).is_err());
+    }
+
+    #[test]
+    fn test_gelf_level_serializes_as_integer_discriminant() {
+        assert_eq!(serde_json::to_value(GelfLevel::Notice).unwrap(), json!(5));
+        assert_eq!(serde_json::to_value(GelfLevel::Emergency).unwrap(), json!(0));
+    }
+
+    #[test]
+    fn test_gelf_level_from_str_accepts_syslog_names() {
+        assert!(matches!("emergency".parse::<GelfLevel>().unwrap(), GelfLevel::Emergency));
+        assert!(matches!("Warning".parse::<GelfLevel>().unwrap(), GelfLevel::Warning));
+        assert!(matches!("info".parse::<GelfLevel>().unwrap(), GelfLevel::Informational));
+        assert!(matches!("informational".parse::<GelfLevel>().unwrap(), GelfLevel::Informational));
+        assert!(matches!("5".parse::<GelfLevel>().unwrap(), GelfLevel::Notice));
+        assert!("nonsense".parse::<GelfLevel>().is_err());
+    }
+
+    #[test]
+    fn test_from_slice_accepts_textual_level() {
+        let r = GelfDataWrapper::from_slice(
+            br#"{
+                "version":"1.1",
+                "host":"example.org",
+                "short_message":"A short message",
+                "level":"warning",
+                "timestamp":1582213226
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(r.data.level, GelfLevel::Warning));
+    }
+
+    #[test]
+    fn test_gelf_to_string_round_trips_level_as_integer() {
+        let r = GelfDataWrapper::from_slice(
+            br#"{
+                "version":"1.1",
+                "host":"example.org",
+                "short_message":"A short message",
+                "level":5,
+                "timestamp":1582213226
+            }"#,
+        )
+        .unwrap();
+
+        let reparsed: Value = serde_json::from_str(&r.to_string()).unwrap();
+        assert_eq!(reparsed["level"], json!(5));
+    }
+
+    #[test]
+    fn test_into_gelf_json_reconstructs_canonical_gelf() {
+        let r = GelfDataWrapper::from_slice(
+            br#"{
+                "version":"1.1",
+                "host":"example.org",
+                "short_message":"A short message",
+                "level":5,
+                "timestamp":1582213226,
+                "_some_info":"foo",
+                "extra_field":"bar"
+            }"#,
+        )
+        .unwrap();
+
+        let canonical = r.into_gelf_json();
+        assert_eq!(canonical["version"], json!("1.1"));
+        assert_eq!(canonical["host"], json!("example.org"));
+        assert_eq!(canonical["short_message"], json!("A short message"));
+        assert_eq!(canonical["level"], json!(5));
+        assert_eq!(canonical["_some_info"], json!("foo"));
+        assert_eq!(canonical["extra_field"], json!("bar"));
+        assert!(canonical.get("meta").is_none());
+        assert!(canonical.get("mechanism_data").is_none());
+
+        let round_tripped: Value = serde_json::from_str(&r.to_gelf_string()).unwrap();
+        assert_eq!(round_tripped, canonical);
+    }
+
+    #[test]
+    fn test_stream_codec_splits_on_null_byte_and_keeps_partial_frame() {
+        let mut codec = GelfStreamCodec::new();
+        let mut buf = BytesMut::from(&b"{\"a\":1}\x00{\"a\":2}\x00{\"a\":3"[..]);
+
+        let GelfMessage(first) = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(first, b"{\"a\":1}");
+
+        let GelfMessage(second) = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(second, b"{\"a\":2}");
+
+        // The trailing partial frame has no delimiter yet.
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert_eq!(&buf[..], b"{\"a\":3");
+
+        buf.extend_from_slice(b"}\x00");
+        let GelfMessage(third) = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(third, b"{\"a\":3}");
+    }
+
+    #[test]
+    fn test_stream_codec_rejects_oversized_frame() {
+        let mut codec = GelfStreamCodec::with_max_frame_length(8);
+        let mut buf = BytesMut::from(&b"0123456789"[..]);
+
+        assert!(codec.decode(&mut buf).is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_chunk_reassembler() {
+        let gelf_reader = GelfReaderActor::new(1);
+        let mut reassembler = GelfChunkReassembler {
+            reader: gelf_reader,
+            timeout: Duration::from_secs(5),
+            pending: HashMap::new(),
+        };
+
+        let message = b"hello chunked gelf world".to_vec();
+        let message_id = [1, 2, 3, 4, 5, 6, 7, 8];
+        let chunks: Vec<&[u8]> = message.chunks(8).collect();
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut datagram = vec![0x1e, 0x0f];
+            datagram.extend_from_slice(&message_id);
+            datagram.push(i as u8);
+            datagram.push(chunks.len() as u8);
+            datagram.extend_from_slice(chunk);
+
+            // Duplicate the first chunk to make sure it's ignored.
+            if i == 0 {
+                assert_eq!(reassembler.process_datagram(datagram.clone()), None);
+            }
+
+            let result = reassembler.process_datagram(datagram);
+            if i + 1 == chunks.len() {
+                assert_eq!(result, Some(message.clone()));
+            } else {
+                assert_eq!(result, None);
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_chunk_reassembler_passthrough() {
+        let gelf_reader = GelfReaderActor::new(1);
+        let mut reassembler = GelfChunkReassembler {
+            reader: gelf_reader,
+            timeout: Duration::from_secs(5),
+            pending: HashMap::new(),
+        };
+
+        let datagram = b"not chunked".to_vec();
+        assert_eq!(reassembler.process_datagram(datagram.clone()), Some(datagram));
+    }
+
+    #[actix_rt::test]
+    async fn test_chunk_reassembler_ignores_mismatched_sequence_count() {
+        let gelf_reader = GelfReaderActor::new(1);
+        let mut reassembler = GelfChunkReassembler {
+            reader: gelf_reader,
+            timeout: Duration::from_secs(5),
+            pending: HashMap::new(),
+        };
+
+        let message_id = [1, 2, 3, 4, 5, 6, 7, 8];
+
+        // First datagram establishes the pending entry with a chunk count of 2.
+        let mut first = vec![0x1e, 0x0f];
+        first.extend_from_slice(&message_id);
+        first.push(0); // sequence_number
+        first.push(2); // sequence_count
+        first.extend_from_slice(b"abc");
+        assert_eq!(reassembler.process_datagram(first), None);
+
+        // A later datagram for the same message id claims a larger sequence
+        // count and an index past the already-allocated chunk vec; it must be
+        // dropped rather than indexing out of bounds.
+        let mut second = vec![0x1e, 0x0f];
+        second.extend_from_slice(&message_id);
+        second.push(5); // sequence_number, out of range for the established entry
+        second.push(10); // sequence_count
+        second.extend_from_slice(b"def");
+        assert_eq!(reassembler.process_datagram(second), None);
+    }
+
+    #[actix_rt::test]
+    async fn test_chunk_reassembler_evicts_expired_pending_messages() {
+        let gelf_reader = GelfReaderActor::new(1);
+        let mut reassembler = GelfChunkReassembler {
+            reader: gelf_reader,
+            timeout: Duration::from_secs(5),
+            pending: HashMap::new(),
+        };
+
+        let mut datagram = vec![0x1e, 0x0f];
+        datagram.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        datagram.push(0); // sequence_number
+        datagram.push(2); // sequence_count
+        datagram.extend_from_slice(b"abc");
+        assert_eq!(reassembler.process_datagram(datagram), None);
+        assert_eq!(reassembler.pending.len(), 1);
+
+        // Backdate the pending message so it's already past the timeout.
+        for pending in reassembler.pending.values_mut() {
+            pending.first_seen = Instant::now() - Duration::from_secs(10);
+        }
+
+        reassembler.evict_expired();
+        assert!(reassembler.pending.is_empty());
+    }
 }